@@ -0,0 +1,9 @@
+pub mod message;
+pub mod payload;
+pub mod record;
+pub mod signature;
+
+pub use message::{NdefMessage, RecordReader};
+pub use payload::*;
+pub use record::{NdefDecodable, NdefEncodable, NdefPayload, NdefRecord, RecordFlags, TNF};
+pub use signature::*;
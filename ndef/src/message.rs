@@ -1,12 +1,85 @@
-use crate::{record::NdefRecord, *};
+use crate::record::{NdefDecodable, NdefRecord};
+use crate::signature::{
+    CertificateFormat, FieldLocation, SignaturePayload, SignatureVerifier, Signer, RTD_SIGNATURE,
+};
+use crate::*;
 use anyhow::{bail, Result};
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 
 #[derive(Default, Debug)]
 pub struct NdefMessage {
     records: Vec<NdefRecord>,
 }
 
+/// Streams [`NdefRecord`]s off a reader one at a time, stopping once a
+/// record with the ME flag has been consumed. Lets callers read a message
+/// off a socket or a large tag dump without buffering it all up front.
+pub struct RecordReader<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read> RecordReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            done: false,
+        }
+    }
+
+    /// Reads one logical record, transparently reassembling it from its
+    /// chunks if the Chunk Flag is set, until a chunk with CF clear is seen.
+    fn read_record(&mut self) -> Result<NdefRecord> {
+        let record = NdefRecord::decode(&mut self.reader)?;
+        if !record.flags().contains(RecordFlags::CF) {
+            return Ok(record);
+        }
+
+        let mut payload = record.payload().to_vec();
+        loop {
+            let chunk = NdefRecord::decode(&mut self.reader)?;
+            if chunk.tnf() != TNF::Unchanged || !chunk.record_type().is_empty() {
+                bail!("chunk continuation must use TNF::Unchanged and carry no type");
+            }
+            if chunk.flags().contains(RecordFlags::MB) {
+                bail!("chunk continuation must not set the MB flag");
+            }
+            if chunk.flags().contains(RecordFlags::ME) && chunk.flags().contains(RecordFlags::CF)
+            {
+                bail!("chunk continuation cannot set ME while CF is still set");
+            }
+            payload.extend_from_slice(chunk.payload());
+            if !chunk.flags().contains(RecordFlags::CF) {
+                let mut flags = record.flags() - RecordFlags::CF;
+                flags.set(RecordFlags::ME, chunk.flags().contains(RecordFlags::ME));
+                return Ok(record.with_reassembled_payload(payload, flags));
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = Result<NdefRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(record) => {
+                if record.flags().contains(RecordFlags::ME) {
+                    self.done = true;
+                }
+                Some(Ok(record))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 impl From<NdefRecord> for NdefMessage {
     fn from(record: NdefRecord) -> Self {
         Self {
@@ -35,6 +108,9 @@ impl NdefMessage {
         &self.records
     }
 
+    /// Thin wrapper over [`NdefEncodable`] that assembles the per-record
+    /// MB/ME flags from each record's position and writes the result into a
+    /// freshly allocated buffer.
     pub fn to_buffer(&self) -> Result<Vec<u8>> {
         let mut buffer = vec![];
         for (index, record) in self.records.iter().enumerate() {
@@ -52,25 +128,141 @@ impl NdefMessage {
         Ok(buffer)
     }
 
+    /// Like [`Self::to_buffer`], but splits any record whose payload exceeds
+    /// `max_chunk_len` into a chunk chain using the Chunk Flag: an initial
+    /// chunk carrying the original type, zero or more middle chunks
+    /// (TNF::Unchanged, no type), and a final chunk with CF clear. MB/ME are
+    /// still carried by the first/last emitted chunk of the whole message.
+    pub fn to_buffer_chunked(&self, max_chunk_len: usize) -> Result<Vec<u8>> {
+        if max_chunk_len == 0 {
+            bail!("max_chunk_len must be greater than zero");
+        }
+        let mut buffer = vec![];
+        let last_record_index = self.records.len().saturating_sub(1);
+        for (index, record) in self.records.iter().enumerate() {
+            let is_first_record = index == 0;
+            let is_last_record = index == last_record_index;
+
+            if record.payload().len() <= max_chunk_len {
+                let mut flag = RecordFlags::empty();
+                flag.set(RecordFlags::MB, is_first_record);
+                flag.set(RecordFlags::ME, is_last_record);
+                buffer.extend_from_slice(&record.to_buffer(flag)?);
+                continue;
+            }
+
+            let chunks: Vec<&[u8]> = record.payload().chunks(max_chunk_len).collect();
+            let last_chunk_index = chunks.len() - 1;
+            for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+                let is_first_chunk = chunk_index == 0;
+                let is_last_chunk = chunk_index == last_chunk_index;
+
+                let chunk_record = record.chunk(chunk.to_vec(), !is_first_chunk);
+                let mut flag = RecordFlags::empty();
+                flag.set(RecordFlags::CF, !is_last_chunk);
+                flag.set(RecordFlags::MB, is_first_record && is_first_chunk);
+                flag.set(RecordFlags::ME, is_last_record && is_last_chunk);
+                buffer.extend_from_slice(&chunk_record.to_buffer(flag)?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    /// Thin wrapper over [`Self::read_from`] for callers that already have
+    /// the whole message buffered in memory. Unlike [`Self::read_from`],
+    /// which only consumes one message and leaves the rest of the stream
+    /// untouched, this requires `data` to be fully consumed, so trailing
+    /// garbage after a well-formed message is rejected rather than
+    /// silently ignored.
     pub fn decode<T: AsRef<[u8]>>(data: T) -> Result<Self> {
-        let total = data.as_ref().len() as u64;
         let mut reader = Cursor::new(data.as_ref());
+        let message = Self::read_from(&mut reader)?;
+        if reader.position() != data.as_ref().len() as u64 {
+            bail!("trailing data after NDEF message");
+        }
+        Ok(message)
+    }
+
+    /// Streams records off `r` via [`RecordReader`], stopping once the
+    /// record carrying the ME flag has been read, without requiring the
+    /// whole message to be buffered up front.
+    pub fn read_from<R: Read>(r: &mut R) -> Result<Self> {
         let mut records = vec![];
-        loop {
-            let record = NdefRecord::decode(&mut reader)?;
-            if record.flags() & RecordFlags::MB == RecordFlags::MB && !records.is_empty() {
+        for record in RecordReader::new(r) {
+            let record = record?;
+            if record.flags().contains(RecordFlags::MB) && !records.is_empty() {
                 bail!("record MB flag is set , but not first record");
             }
-            let flags = record.flags();
             records.push(record);
-            if reader.position() >= total {
-                if flags & RecordFlags::ME != RecordFlags::ME {
-                    bail!("record ME flag is not set")
-                }
-                break;
+        }
+        match records.last() {
+            Some(last) if last.flags().contains(RecordFlags::ME) => Ok(Self { records }),
+            _ => bail!("record ME flag is not set"),
+        }
+    }
+
+    /// Verifies every NFC Forum Signature RTD record in this message against
+    /// the records that precede it, using `verifier` as the crypto backend.
+    /// Returns `Ok(true)` only if every signature present verifies.
+    pub fn verify_signatures(&self, verifier: &impl SignatureVerifier) -> Result<bool> {
+        for (index, record) in self.records.iter().enumerate() {
+            if record.tnf() != TNF::WellKnown || record.record_type() != RTD_SIGNATURE.as_bytes() {
+                continue;
+            }
+            let payload = SignaturePayload::try_from(record)?;
+            let signature = match payload.signature() {
+                FieldLocation::Inline(bytes) => bytes.clone(),
+                FieldLocation::Uri(_) => bail!("signatures fetched from a URI are not supported"),
+            };
+            let signed_data = self.signed_data_before(index)?;
+            if !verifier.verify(&signed_data, &signature, payload.signature_type())? {
+                return Ok(false);
             }
         }
-        Ok(Self { records })
+        Ok(true)
+    }
+
+    /// Appends a Signature RTD record produced by `signer` over `records`,
+    /// returning the resulting message.
+    pub fn sign(records: impl Into<Vec<NdefRecord>>, signer: &impl Signer) -> Result<Self> {
+        let mut message = Self {
+            records: records.into(),
+        };
+        let signed_data = message.signed_data_before(message.records.len())?;
+        let signature = signer.sign(&signed_data)?;
+        let payload = SignaturePayload::new(
+            signer.signature_type(),
+            FieldLocation::Inline(signature),
+            CertificateFormat::X509,
+            vec![],
+        )?;
+        message.add_record(
+            NdefRecord::builder()
+                .tnf(TNF::WellKnown)
+                .payload(&payload)
+                .build()?,
+        );
+        Ok(message)
+    }
+
+    /// Concatenates the encoded bytes of `self.records[..end]`, starting
+    /// from the record bearing the MB flag, as specified for Signature RTD
+    /// signed data.
+    fn signed_data_before(&self, end: usize) -> Result<Vec<u8>> {
+        let start = self.records[..end]
+            .iter()
+            .position(|r| r.flags().contains(RecordFlags::MB))
+            .unwrap_or(0);
+        let mut data = vec![];
+        for (offset, record) in self.records[start..end].iter().enumerate() {
+            let flag = if offset == 0 {
+                RecordFlags::MB
+            } else {
+                RecordFlags::empty()
+            };
+            data.extend_from_slice(&record.to_buffer(flag)?);
+        }
+        Ok(data)
     }
 }
 
@@ -81,6 +273,7 @@ mod tests {
     use crate::payload::*;
     use crate::record::NdefRecord;
     use crate::*;
+    use anyhow::Result;
 
     #[test]
     fn test_multiple_records() {
@@ -169,4 +362,100 @@ mod tests {
         let message = NdefMessage::decode(hex::decode(expect).unwrap()).unwrap();
         assert_eq!(3, message.records().len());
     }
+
+    #[test]
+    fn test_record_reader_streams_without_buffering_whole_message() {
+        let record1 = NdefRecord::builder()
+            .tnf(TNF::WellKnown)
+            .payload(&UriPayload::from_static("weixin://dl/business"))
+            .build()
+            .unwrap();
+        let record2 = NdefRecord::builder()
+            .tnf(TNF::External)
+            .payload(&ExternalPayload::from_static(
+                b"android.com:pkg",
+                b"com.tencent.mm",
+            ))
+            .build()
+            .unwrap();
+        let buffer = NdefMessage::from(&[record1, record2]).to_buffer().unwrap();
+
+        let mut cursor = std::io::Cursor::new(&buffer);
+        let records: Vec<NdefRecord> = RecordReader::new(&mut cursor)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(TNF::WellKnown, records[0].tnf());
+        assert_eq!(TNF::External, records[1].tnf());
+    }
+
+    #[test]
+    fn test_decode_rejects_trailing_garbage() {
+        let record = NdefRecord::builder()
+            .tnf(TNF::WellKnown)
+            .payload(&UriPayload::from_static("weixin://dl/business"))
+            .build()
+            .unwrap();
+        let mut buffer = NdefMessage::from(record).to_buffer().unwrap();
+        buffer.extend_from_slice(&[0u8; 37]);
+
+        assert!(NdefMessage::decode(buffer).is_err());
+    }
+
+    #[test]
+    fn test_chunked_round_trip() {
+        let record1 = NdefRecord::builder()
+            .tnf(TNF::WellKnown)
+            .payload(&UriPayload::from_static("weixin://dl/business"))
+            .build()
+            .unwrap();
+        let record2 = NdefRecord::builder()
+            .tnf(TNF::External)
+            .payload(&ExternalPayload::from_static(
+                b"android.com:pkg",
+                b"com.tencent.mm",
+            ))
+            .build()
+            .unwrap();
+        let message = NdefMessage::from(&[record1, record2]);
+
+        let buffer = message.to_buffer_chunked(5).unwrap();
+        assert_ne!(buffer, message.to_buffer().unwrap());
+
+        let decoded = NdefMessage::decode(buffer).unwrap();
+        assert_eq!(2, decoded.records().len());
+        assert_eq!(TNF::WellKnown, decoded.records()[0].tnf());
+        let payload = UriPayload::try_from(&decoded.records()[0]).unwrap();
+        assert_eq!("weixin://dl/business", payload.uri());
+        assert_eq!(TNF::External, decoded.records()[1].tnf());
+        assert_eq!(b"com.tencent.mm", decoded.records()[1].payload());
+    }
+
+    #[test]
+    fn test_chunk_continuation_cannot_set_me_while_cf_set() {
+        let record = NdefRecord::builder()
+            .tnf(TNF::WellKnown)
+            .payload(&UriPayload::from_static("hi"))
+            .build()
+            .unwrap();
+        let first = record.chunk(b"h".to_vec(), false);
+        let second = record.chunk(b"i".to_vec(), true);
+
+        let mut buffer = first.to_buffer(RecordFlags::MB | RecordFlags::CF).unwrap();
+        // Invalid: a continuation chunk must not set ME while CF is still set.
+        buffer.extend_from_slice(&second.to_buffer(RecordFlags::CF | RecordFlags::ME).unwrap());
+
+        assert!(NdefMessage::read_from(&mut std::io::Cursor::new(&buffer)).is_err());
+    }
+
+    #[test]
+    fn test_encode_rejects_oversized_record_type() {
+        let record = NdefRecord::builder()
+            .tnf(TNF::Media)
+            .payload(&MediaPayload::new("x".repeat(256), vec![0x01]))
+            .build()
+            .unwrap();
+
+        assert!(record.to_buffer(RecordFlags::MB | RecordFlags::ME).is_err());
+    }
 }
@@ -0,0 +1,580 @@
+use crate::message::NdefMessage;
+use crate::record::{NdefPayload, NdefRecord};
+use crate::TNF;
+use anyhow::{bail, Context, Result};
+
+pub const RTD_URI: &str = "U";
+pub const RTD_TEXT: &str = "T";
+pub const RTD_SMART_POSTER: &str = "Sp";
+pub const RTD_ACTION: &str = "act";
+pub const RTD_SIZE: &str = "s";
+pub const RTD_TYPE: &str = "t";
+
+pub const NONE_ABBRE: u8 = 0x00;
+pub const HTTP_WWW: u8 = 0x01;
+pub const HTTPS_WWW: u8 = 0x02;
+pub const HTTP: u8 = 0x03;
+pub const HTTPS: u8 = 0x04;
+pub const TEL: u8 = 0x05;
+pub const MAILTO: u8 = 0x06;
+
+fn abbreviation_prefix(abbreviation: u8) -> &'static str {
+    match abbreviation {
+        HTTP_WWW => "http://www.",
+        HTTPS_WWW => "https://www.",
+        HTTP => "http://",
+        HTTPS => "https://",
+        TEL => "tel:",
+        MAILTO => "mailto:",
+        _ => "",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UriPayload {
+    abbreviation: u8,
+    uri: String,
+}
+
+impl UriPayload {
+    pub fn from_static(uri: impl Into<String>) -> Self {
+        Self {
+            abbreviation: NONE_ABBRE,
+            uri: uri.into(),
+        }
+    }
+
+    pub fn with_abbrev(abbreviation: u8, uri: impl Into<String>) -> Self {
+        Self {
+            abbreviation,
+            uri: uri.into(),
+        }
+    }
+
+    pub fn abbreviation(&self) -> u8 {
+        self.abbreviation
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn full_uri(&self) -> String {
+        format!("{}{}", abbreviation_prefix(self.abbreviation), self.uri)
+    }
+}
+
+impl NdefPayload for UriPayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_URI.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.abbreviation];
+        bytes.extend_from_slice(self.uri.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&NdefRecord> for UriPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(record: &NdefRecord) -> Result<Self> {
+        if record.tnf() != TNF::WellKnown || record.record_type() != RTD_URI.as_bytes() {
+            bail!("record is not a URI record");
+        }
+        let payload = record.payload();
+        if payload.is_empty() {
+            bail!("URI payload is empty");
+        }
+        Ok(Self {
+            abbreviation: payload[0],
+            uri: String::from_utf8(payload[1..].to_vec())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExternalPayload {
+    record_type: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl ExternalPayload {
+    pub fn from_static(record_type: impl Into<Vec<u8>>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            record_type: record_type.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl NdefPayload for ExternalPayload {
+    fn record_type(&self) -> Vec<u8> {
+        self.record_type.clone()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+/// A Text RTD ("T") payload: an ISO/IANA language code plus the text
+/// itself, used by Smart Poster titles among others.
+#[derive(Debug, Clone)]
+pub struct TextPayload {
+    language: String,
+    text: String,
+}
+
+impl TextPayload {
+    pub fn new(language: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            text: text.into(),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl NdefPayload for TextPayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_TEXT.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let language = self.language.as_bytes();
+        let mut bytes = vec![language.len() as u8];
+        bytes.extend_from_slice(language);
+        bytes.extend_from_slice(self.text.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&NdefRecord> for TextPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(record: &NdefRecord) -> Result<Self> {
+        if record.tnf() != TNF::WellKnown || record.record_type() != RTD_TEXT.as_bytes() {
+            bail!("record is not a Text record");
+        }
+        let payload = record.payload();
+        let status = *payload.first().context("Text payload is empty")?;
+        if status & 0x80 != 0 {
+            bail!("UTF-16 Text records are not supported");
+        }
+        let language_length = (status & 0x3f) as usize;
+        let language_end = 1 + language_length;
+        let language = payload
+            .get(1..language_end)
+            .context("Text payload is shorter than its language code")?;
+        let text = payload
+            .get(language_end..)
+            .context("Text payload is shorter than its language code")?;
+        Ok(Self {
+            language: String::from_utf8(language.to_vec())?,
+            text: String::from_utf8(text.to_vec())?,
+        })
+    }
+}
+
+/// The Action RTD ("act") single-byte action that a Smart Poster
+/// recommends a reader take with its URI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartPosterAction {
+    Do,
+    Save,
+    Edit,
+}
+
+impl SmartPosterAction {
+    fn value(self) -> u8 {
+        match self {
+            SmartPosterAction::Do => 0,
+            SmartPosterAction::Save => 1,
+            SmartPosterAction::Edit => 2,
+        }
+    }
+
+    fn from_value(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(SmartPosterAction::Do),
+            1 => Ok(SmartPosterAction::Save),
+            2 => Ok(SmartPosterAction::Edit),
+            other => bail!("unknown Smart Poster action {other}"),
+        }
+    }
+}
+
+struct ActionPayload(SmartPosterAction);
+
+impl NdefPayload for ActionPayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_ACTION.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.0.value()]
+    }
+}
+
+struct SizePayload(u32);
+
+impl NdefPayload for SizePayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_SIZE.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+struct TypePayload(String);
+
+impl NdefPayload for TypePayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_TYPE.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+}
+
+/// A Smart Poster ("Sp") payload. It is itself an encapsulated NDEF message
+/// carrying a mandatory URI record plus optional Title, Action, Size, Type
+/// and Icon records; see [`Self::message`] and the typed accessors to
+/// introspect it, or [`SmartPosterBuilder`] to assemble one.
+#[derive(Debug, Clone)]
+pub struct SmartPosterPayload {
+    data: Vec<u8>,
+}
+
+impl SmartPosterPayload {
+    pub fn from_static(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into() }
+    }
+
+    pub fn builder() -> SmartPosterBuilder {
+        SmartPosterBuilder::default()
+    }
+
+    /// Decodes the nested NDEF message carried by this Smart Poster.
+    pub fn message(&self) -> Result<NdefMessage> {
+        NdefMessage::decode(&self.data)
+    }
+
+    fn find<'a>(message: &'a NdefMessage, record_type: &str) -> Option<&'a NdefRecord> {
+        message
+            .records()
+            .iter()
+            .find(|record| record.tnf() == TNF::WellKnown && record.record_type() == record_type.as_bytes())
+    }
+
+    pub fn uri(&self) -> Result<UriPayload> {
+        let message = self.message()?;
+        let record = Self::find(&message, RTD_URI).context("Smart Poster has no URI record")?;
+        UriPayload::try_from(record)
+    }
+
+    pub fn titles(&self) -> Result<Vec<TextPayload>> {
+        let message = self.message()?;
+        message
+            .records()
+            .iter()
+            .filter(|record| record.tnf() == TNF::WellKnown && record.record_type() == RTD_TEXT.as_bytes())
+            .map(TextPayload::try_from)
+            .collect()
+    }
+
+    pub fn action(&self) -> Result<Option<SmartPosterAction>> {
+        let message = self.message()?;
+        Self::find(&message, RTD_ACTION)
+            .map(|record| {
+                let byte = *record.payload().first().context("Action payload is empty")?;
+                SmartPosterAction::from_value(byte)
+            })
+            .transpose()
+    }
+
+    pub fn size(&self) -> Result<Option<u32>> {
+        let message = self.message()?;
+        Self::find(&message, RTD_SIZE)
+            .map(|record| {
+                let bytes: [u8; 4] = record
+                    .payload()
+                    .try_into()
+                    .context("Size payload must be 4 bytes")?;
+                Ok(u32::from_be_bytes(bytes))
+            })
+            .transpose()
+    }
+
+    pub fn mime_type(&self) -> Result<Option<String>> {
+        let message = self.message()?;
+        Self::find(&message, RTD_TYPE)
+            .map(|record| Ok(String::from_utf8(record.payload().to_vec())?))
+            .transpose()
+    }
+
+    /// The Icon record, if present: a [`TNF::Media`] record whose MIME type
+    /// starts with `image/`.
+    pub fn icon(&self) -> Result<Option<MediaPayload>> {
+        let message = self.message()?;
+        message
+            .records()
+            .iter()
+            .find(|record| record.tnf() == TNF::Media && record.record_type().starts_with(b"image/"))
+            .map(MediaPayload::try_from)
+            .transpose()
+    }
+}
+
+impl NdefPayload for SmartPosterPayload {
+    fn record_type(&self) -> Vec<u8> {
+        RTD_SMART_POSTER.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+/// Assembles a [`SmartPosterPayload`] from its sub-records.
+#[derive(Default)]
+pub struct SmartPosterBuilder {
+    uri: Option<UriPayload>,
+    titles: Vec<TextPayload>,
+    action: Option<SmartPosterAction>,
+    size: Option<u32>,
+    mime_type: Option<String>,
+    icon: Option<MediaPayload>,
+}
+
+impl SmartPosterBuilder {
+    pub fn uri(mut self, uri: UriPayload) -> Self {
+        self.uri = Some(uri);
+        self
+    }
+
+    pub fn title(mut self, title: TextPayload) -> Self {
+        self.titles.push(title);
+        self
+    }
+
+    pub fn action(mut self, action: SmartPosterAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    pub fn icon(mut self, icon: MediaPayload) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn build(self) -> Result<SmartPosterPayload> {
+        let uri = self.uri.context("Smart Poster requires a URI record")?;
+        let mut message = NdefMessage::from(NdefRecord::builder().tnf(TNF::WellKnown).payload(&uri).build()?);
+        for title in &self.titles {
+            message.add_record(NdefRecord::builder().tnf(TNF::WellKnown).payload(title).build()?);
+        }
+        if let Some(action) = self.action {
+            message.add_record(
+                NdefRecord::builder()
+                    .tnf(TNF::WellKnown)
+                    .payload(&ActionPayload(action))
+                    .build()?,
+            );
+        }
+        if let Some(size) = self.size {
+            message.add_record(
+                NdefRecord::builder()
+                    .tnf(TNF::WellKnown)
+                    .payload(&SizePayload(size))
+                    .build()?,
+            );
+        }
+        if let Some(mime_type) = self.mime_type {
+            message.add_record(
+                NdefRecord::builder()
+                    .tnf(TNF::WellKnown)
+                    .payload(&TypePayload(mime_type))
+                    .build()?,
+            );
+        }
+        if let Some(icon) = &self.icon {
+            message.add_record(NdefRecord::builder().tnf(TNF::Media).payload(icon).build()?);
+        }
+        Ok(SmartPosterPayload {
+            data: message.to_buffer()?,
+        })
+    }
+}
+
+pub const MIME_BLUETOOTH_LE_OOB: &str = "application/vnd.bluetooth.le.oob";
+pub const MIME_WIFI_SIMPLE_CONFIG: &str = "application/vnd.wfa.wsc";
+
+/// A content type recognized by [`MediaPayload::known_kind`], letting
+/// callers match well-known handover MIME types instead of comparing raw
+/// strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownMediaKind {
+    BluetoothLeOob,
+    WifiSimpleConfig,
+}
+
+impl KnownMediaKind {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            KnownMediaKind::BluetoothLeOob => MIME_BLUETOOTH_LE_OOB,
+            KnownMediaKind::WifiSimpleConfig => MIME_WIFI_SIMPLE_CONFIG,
+        }
+    }
+
+    fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            MIME_BLUETOOTH_LE_OOB => Some(KnownMediaKind::BluetoothLeOob),
+            MIME_WIFI_SIMPLE_CONFIG => Some(KnownMediaKind::WifiSimpleConfig),
+            _ => None,
+        }
+    }
+}
+
+/// A TNF::Media record carrying an RFC 2046 media type in its record-type
+/// field, e.g. `application/json` or `image/png`.
+#[derive(Debug, Clone)]
+pub struct MediaPayload {
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+impl MediaPayload {
+    pub fn new(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Matches this payload's MIME type against the known-kind registry,
+    /// e.g. BLE OOB or Wi-Fi Simple Config handover payloads.
+    pub fn known_kind(&self) -> Option<KnownMediaKind> {
+        KnownMediaKind::from_mime_type(&self.mime_type)
+    }
+}
+
+impl NdefPayload for MediaPayload {
+    fn record_type(&self) -> Vec<u8> {
+        self.mime_type.as_bytes().to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+impl TryFrom<&NdefRecord> for MediaPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(record: &NdefRecord) -> Result<Self> {
+        if record.tnf() != TNF::Media {
+            bail!("record is not a Media record");
+        }
+        Ok(Self {
+            mime_type: String::from_utf8(record.record_type().to_vec())?,
+            data: record.payload().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smart_poster_round_trip() {
+        let poster = SmartPosterPayload::builder()
+            .uri(UriPayload::with_abbrev(HTTP_WWW, "example.com".to_string()))
+            .title(TextPayload::new("en", "Example"))
+            .action(SmartPosterAction::Do)
+            .size(1234)
+            .mime_type("text/html")
+            .icon(MediaPayload::new("image/png", vec![0x89, 0x50, 0x4e, 0x47]))
+            .build()
+            .unwrap();
+
+        assert_eq!("http://www.example.com", poster.uri().unwrap().full_uri());
+        assert_eq!(1, poster.titles().unwrap().len());
+        assert_eq!("Example", poster.titles().unwrap()[0].text());
+        assert_eq!(Some(SmartPosterAction::Do), poster.action().unwrap());
+        assert_eq!(Some(1234), poster.size().unwrap());
+        assert_eq!(Some("text/html".to_string()), poster.mime_type().unwrap());
+        let icon = poster.icon().unwrap().unwrap();
+        assert_eq!("image/png", icon.mime_type());
+        assert_eq!(&[0x89, 0x50, 0x4e, 0x47], icon.data());
+
+        let record = NdefRecord::builder()
+            .tnf(TNF::External)
+            .payload(&poster)
+            .build()
+            .unwrap();
+        let roundtripped = SmartPosterPayload::from_static(record.payload().to_vec());
+        assert_eq!("http://www.example.com", roundtripped.uri().unwrap().full_uri());
+    }
+
+    #[test]
+    fn test_smart_poster_builder_requires_uri() {
+        assert!(SmartPosterPayload::builder().title(TextPayload::new("en", "Example")).build().is_err());
+    }
+
+    #[test]
+    fn test_media_payload_round_trip() {
+        let payload = MediaPayload::new(MIME_WIFI_SIMPLE_CONFIG, vec![0x01, 0x02, 0x03]);
+        let record = NdefRecord::builder().tnf(TNF::Media).payload(&payload).build().unwrap();
+
+        let decoded = MediaPayload::try_from(&record).unwrap();
+        assert_eq!(MIME_WIFI_SIMPLE_CONFIG, decoded.mime_type());
+        assert_eq!(&[0x01, 0x02, 0x03], decoded.data());
+        assert_eq!(Some(KnownMediaKind::WifiSimpleConfig), decoded.known_kind());
+    }
+
+    #[test]
+    fn test_media_payload_rejects_non_media_tnf() {
+        let record = NdefRecord::builder()
+            .tnf(TNF::WellKnown)
+            .payload(&UriPayload::from_static("https://example.com"))
+            .build()
+            .unwrap();
+        assert!(MediaPayload::try_from(&record).is_err());
+    }
+}
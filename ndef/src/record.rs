@@ -0,0 +1,310 @@
+use anyhow::{bail, Context, Result};
+use bitflags::bitflags;
+use std::io::{Read, Write};
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct RecordFlags: u8 {
+        const MB = 0b1000_0000;
+        const ME = 0b0100_0000;
+        const CF = 0b0010_0000;
+        const SR = 0b0001_0000;
+        const IL = 0b0000_1000;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TNF {
+    #[default]
+    Empty,
+    WellKnown,
+    Media,
+    AbsoluteUri,
+    External,
+    Unknown,
+    Unchanged,
+    Reserved,
+}
+
+impl TNF {
+    fn value(self) -> u8 {
+        match self {
+            TNF::Empty => 0x00,
+            TNF::WellKnown => 0x01,
+            TNF::Media => 0x02,
+            TNF::AbsoluteUri => 0x03,
+            TNF::External => 0x04,
+            TNF::Unknown => 0x05,
+            TNF::Unchanged => 0x06,
+            TNF::Reserved => 0x07,
+        }
+    }
+}
+
+impl From<u8> for TNF {
+    fn from(value: u8) -> Self {
+        match value & 0x07 {
+            0x00 => TNF::Empty,
+            0x01 => TNF::WellKnown,
+            0x02 => TNF::Media,
+            0x03 => TNF::AbsoluteUri,
+            0x04 => TNF::External,
+            0x05 => TNF::Unknown,
+            0x06 => TNF::Unchanged,
+            _ => TNF::Reserved,
+        }
+    }
+}
+
+/// A record-level codec for reading a single [`NdefRecord`] off any `Read`,
+/// mirroring `consensus::encode`'s `Decodable` trait from rust-bitcoin.
+pub trait NdefDecodable: Sized {
+    fn decode<R: Read>(r: &mut R) -> Result<Self>;
+}
+
+/// The encode-side counterpart of [`NdefDecodable`].
+pub trait NdefEncodable {
+    fn encode<W: Write>(&self, w: &mut W) -> Result<usize>;
+}
+
+/// Types that can provide the record type and payload bytes of an
+/// [`NdefRecord`], used by [`NdefRecordBuilder::payload`].
+pub trait NdefPayload {
+    fn record_type(&self) -> Vec<u8>;
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NdefRecord {
+    tnf: TNF,
+    flags: RecordFlags,
+    record_type: Vec<u8>,
+    id: Option<Vec<u8>>,
+    payload: Vec<u8>,
+}
+
+impl NdefRecord {
+    pub fn builder() -> NdefRecordBuilder {
+        NdefRecordBuilder::default()
+    }
+
+    pub fn tnf(&self) -> TNF {
+        self.tnf
+    }
+
+    pub fn flags(&self) -> RecordFlags {
+        self.flags
+    }
+
+    pub fn record_type(&self) -> &[u8] {
+        &self.record_type
+    }
+
+    pub fn id(&self) -> Option<&[u8]> {
+        self.id.as_deref()
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Encodes the record with `message_flags` (typically MB/ME, decided by
+    /// the containing [`crate::NdefMessage`] from the record's position, plus
+    /// CF for chunked encoding). These replace any MB/ME/CF flags already set
+    /// on the record; SR and IL are derived from the payload length and id.
+    pub fn to_buffer(&self, message_flags: RecordFlags) -> Result<Vec<u8>> {
+        let mut buffer = vec![];
+        self.with_flags(message_flags).encode(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn with_flags(&self, extra: RecordFlags) -> NdefRecord {
+        let mut record = self.clone();
+        record.flags -= RecordFlags::MB | RecordFlags::ME | RecordFlags::CF;
+        record.flags |= extra;
+        record
+    }
+
+    /// Builds one chunk of this record's payload for chunked encoding. The
+    /// first chunk keeps the original TNF/type/id; continuation chunks use
+    /// TNF::Unchanged and no type, per the Chunk Flag framing. Flags (CF,
+    /// MB, ME) are left empty here and applied by the caller via
+    /// [`Self::to_buffer`].
+    pub(crate) fn chunk(&self, payload: Vec<u8>, continuation: bool) -> NdefRecord {
+        if continuation {
+            NdefRecord {
+                tnf: TNF::Unchanged,
+                flags: RecordFlags::empty(),
+                record_type: vec![],
+                id: None,
+                payload,
+            }
+        } else {
+            NdefRecord {
+                tnf: self.tnf,
+                flags: RecordFlags::empty(),
+                record_type: self.record_type.clone(),
+                id: self.id.clone(),
+                payload,
+            }
+        }
+    }
+
+    /// Rebuilds this record with a reassembled payload and final flags,
+    /// used once a chunked record's continuation chunks have all been read.
+    pub(crate) fn with_reassembled_payload(&self, payload: Vec<u8>, flags: RecordFlags) -> NdefRecord {
+        let mut record = self.clone();
+        record.payload = payload;
+        record.flags = flags;
+        record
+    }
+}
+
+impl NdefEncodable for NdefRecord {
+    fn encode<W: Write>(&self, w: &mut W) -> Result<usize> {
+        if self.record_type.len() > u8::MAX as usize {
+            bail!("record type is longer than {} bytes", u8::MAX);
+        }
+        if let Some(id) = &self.id {
+            if id.len() > u8::MAX as usize {
+                bail!("record id is longer than {} bytes", u8::MAX);
+            }
+        }
+
+        let short_record = self.payload.len() <= u8::MAX as usize;
+        let mut flags = self.flags & (RecordFlags::MB | RecordFlags::ME | RecordFlags::CF);
+        flags.set(RecordFlags::SR, short_record);
+        flags.set(RecordFlags::IL, self.id.is_some());
+
+        let mut written = 0;
+        let header = flags.bits() | self.tnf.value();
+        w.write_all(&[header])?;
+        written += 1;
+
+        w.write_all(&[self.record_type.len() as u8])?;
+        written += 1;
+
+        if short_record {
+            w.write_all(&[self.payload.len() as u8])?;
+            written += 1;
+        } else {
+            w.write_all(&(self.payload.len() as u32).to_le_bytes())?;
+            written += 4;
+        }
+
+        if let Some(id) = &self.id {
+            w.write_all(&[id.len() as u8])?;
+            written += 1;
+        }
+
+        w.write_all(&self.record_type)?;
+        written += self.record_type.len();
+
+        if let Some(id) = &self.id {
+            w.write_all(id)?;
+            written += id.len();
+        }
+
+        w.write_all(&self.payload)?;
+        written += self.payload.len();
+
+        Ok(written)
+    }
+}
+
+impl NdefDecodable for NdefRecord {
+    fn decode<R: Read>(r: &mut R) -> Result<Self> {
+        let mut header = [0u8; 1];
+        r.read_exact(&mut header).context("reading record header")?;
+        let tnf = TNF::from(header[0]);
+        let flags = RecordFlags::from_bits_truncate(header[0] & !0x07);
+
+        let mut one = [0u8; 1];
+        r.read_exact(&mut one).context("reading type length")?;
+        let type_length = one[0] as usize;
+
+        let payload_length = if flags.contains(RecordFlags::SR) {
+            r.read_exact(&mut one).context("reading payload length")?;
+            one[0] as usize
+        } else {
+            let mut buf = [0u8; 4];
+            r.read_exact(&mut buf).context("reading payload length")?;
+            u32::from_le_bytes(buf) as usize
+        };
+
+        let id_length = if flags.contains(RecordFlags::IL) {
+            r.read_exact(&mut one).context("reading id length")?;
+            one[0] as usize
+        } else {
+            0
+        };
+
+        let mut record_type = vec![0u8; type_length];
+        r.read_exact(&mut record_type).context("reading record type")?;
+
+        let id = if flags.contains(RecordFlags::IL) {
+            let mut id = vec![0u8; id_length];
+            r.read_exact(&mut id).context("reading record id")?;
+            Some(id)
+        } else {
+            None
+        };
+
+        let mut payload = vec![0u8; payload_length];
+        r.read_exact(&mut payload).context("reading payload")?;
+
+        if type_length > 0 && tnf == TNF::Unchanged {
+            bail!("record with TNF Unchanged must not carry a type");
+        }
+
+        Ok(NdefRecord {
+            tnf,
+            flags,
+            record_type,
+            id,
+            payload,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct NdefRecordBuilder {
+    tnf: TNF,
+    flags: RecordFlags,
+    record_type: Vec<u8>,
+    id: Option<Vec<u8>>,
+    payload: Vec<u8>,
+}
+
+impl NdefRecordBuilder {
+    pub fn tnf(mut self, tnf: TNF) -> Self {
+        self.tnf = tnf;
+        self
+    }
+
+    pub fn flags(mut self, flags: RecordFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn id(mut self, id: impl Into<Vec<u8>>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn payload(mut self, payload: &impl NdefPayload) -> Self {
+        self.record_type = payload.record_type();
+        self.payload = payload.to_bytes();
+        self
+    }
+
+    pub fn build(self) -> Result<NdefRecord> {
+        Ok(NdefRecord {
+            tnf: self.tnf,
+            flags: self.flags,
+            record_type: self.record_type,
+            id: self.id,
+            payload: self.payload,
+        })
+    }
+}